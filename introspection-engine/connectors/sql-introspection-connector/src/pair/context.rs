@@ -0,0 +1,97 @@
+use psl::datamodel_connector::Connector;
+use sql_schema_describer as sql;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use super::model::NameCollisionStrategy;
+
+/// The Prisma name of a table (or other top-level item), alongside the `@map`-ed database name it
+/// needs, if any. Returned by [`Context::table_prisma_name`].
+#[derive(Debug, Clone)]
+pub(crate) struct TablePrismaName<'a> {
+    prisma_name: Cow<'a, str>,
+    mapped_name: Option<&'a str>,
+}
+
+impl<'a> TablePrismaName<'a> {
+    pub(crate) fn prisma_name(&self) -> Cow<'a, str> {
+        self.prisma_name.clone()
+    }
+
+    pub(crate) fn mapped_name(&self) -> Option<&'a str> {
+        self.mapped_name
+    }
+}
+
+/// Per-run state and configuration shared by every [`super::Pair`] produced while introspecting a
+/// single database: the active connector, precomputed name lookups, and the flags that change how a
+/// [`super::model::ModelPair`] renders - including [`Self::infers_id_from_unique_index`] and
+/// [`Self::name_collision_strategy`].
+///
+/// Only part of the surface [`super::model::ModelPair`] consults lives here; the rest of the
+/// connector's context - previous-schema lookups for relation/scalar-field matching, and the
+/// per-table foreign-key and relation lookups behind `ModelPair::relation_fields`, namely - is
+/// outside the slice of the crate checked into this source tree.
+pub(crate) struct Context<'a> {
+    active_connector: &'a dyn Connector,
+    table_names: HashMap<sql::TableId, TablePrismaName<'a>>,
+    duplicate_names: HashSet<&'a str>,
+    uses_namespaces: bool,
+    infers_id_from_unique_index: bool,
+    name_collision_strategy: NameCollisionStrategy,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(
+        active_connector: &'a dyn Connector,
+        table_names: HashMap<sql::TableId, TablePrismaName<'a>>,
+        duplicate_names: HashSet<&'a str>,
+        uses_namespaces: bool,
+        infers_id_from_unique_index: bool,
+        name_collision_strategy: NameCollisionStrategy,
+    ) -> Self {
+        Self {
+            active_connector,
+            table_names,
+            duplicate_names,
+            uses_namespaces,
+            infers_id_from_unique_index,
+            name_collision_strategy,
+        }
+    }
+
+    pub(crate) fn active_connector(&self) -> &'a dyn Connector {
+        self.active_connector
+    }
+
+    /// True, if the connector/schema combination this run is introspecting uses the multi-schema
+    /// feature, and namespaces should be consulted at all.
+    pub(crate) fn uses_namespaces(&self) -> bool {
+        self.uses_namespaces
+    }
+
+    /// The Prisma name a table should get, precomputed once per run so every [`super::Pair`] that
+    /// needs it is a cheap lookup instead of redoing sanitization/collision-detection work per field.
+    pub(crate) fn table_prisma_name(&self, id: sql::TableId) -> &TablePrismaName<'a> {
+        &self.table_names[&id]
+    }
+
+    /// False if another top-level item, defined in a different namespace, already uses `name`.
+    pub(crate) fn name_is_unique(&self, name: &str) -> bool {
+        !self.duplicate_names.contains(name)
+    }
+
+    /// Whether a model with no primary key at all should have one inferred from a qualifying unique
+    /// index - see [`super::model::ModelPair::promoted_unique_index_as_id`].
+    pub(crate) fn infers_id_from_unique_index(&self) -> bool {
+        self.infers_id_from_unique_index
+    }
+
+    /// How to resolve a Prisma-name collision between top-level items defined in different
+    /// namespaces - see [`super::model::ModelPair::name`].
+    pub(crate) fn name_collision_strategy(&self) -> NameCollisionStrategy {
+        self.name_collision_strategy
+    }
+}
@@ -10,6 +10,60 @@ use super::{IdPair, IndexPair, Pair, RelationFieldDirection, RelationFieldPair,
 
 pub(crate) type ModelPair<'a> = Pair<'a, walkers::ModelWalker<'a>, sql::TableWalker<'a>>;
 
+/// How to resolve a model (or other top-level item) whose Prisma name clashes with another
+/// top-level item defined in a different namespace. Configured on the introspection context and
+/// consulted from [`ModelPair::name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum NameCollisionStrategy {
+    /// Leave the colliding item to be ignored/renamed by the caller. The legacy behavior, and the
+    /// only sound choice for connectors that don't support multiple namespaces.
+    #[default]
+    Ignore,
+    /// Prefix the item's Prisma name with its namespace, producing a unique, `@@map`-backed name so
+    /// that multi-schema databases with identically named tables in different schemas introspect
+    /// into usable, compilable models instead of being skipped.
+    PrefixWithNamespace,
+}
+
+/// A table-level CHECK constraint, mirroring the shape of [`IndexPair`].
+///
+/// The PSL doesn't have a dedicated node for `@@check` yet, so there is nothing on the `previous`
+/// side to match a database constraint up with: every occurrence is always new. The pairing is kept
+/// here regardless, so that introspection can still decide, from a single iterator, whether to emit
+/// a `@@check(...)` attribute or fall back to preserving the raw expression as a comment - and so a
+/// future PSL-side representation can slot into the `previous` side without changing this shape.
+pub(crate) type CheckConstraintPair<'a> = Pair<'a, (), sql::CheckConstraintWalker<'a>>;
+
+impl<'a> CheckConstraintPair<'a> {
+    /// The name of the constraint, if the database gave it one.
+    pub(crate) fn name(self) -> Option<&'a str> {
+        self.next.name()
+    }
+
+    /// The raw CHECK expression as reported by the database.
+    pub(crate) fn expression(self) -> &'a str {
+        self.next.expression()
+    }
+
+    /// Renders this constraint into the text the PSL renderer should emit for it: a `@@check(...)`
+    /// attribute, or - if the raw expression contains characters that couldn't round-trip through one
+    /// written this crudely (an embedded `"` or `)`) - a comment preserving the raw expression instead.
+    /// Either way, the constraint survives in the introspected schema instead of being silently
+    /// dropped on re-introspection.
+    pub(crate) fn render(self) -> String {
+        let expression = self.expression();
+
+        if expression.contains('"') || expression.contains(')') {
+            return format!("// CHECK ({expression})");
+        }
+
+        match self.name() {
+            Some(name) => format!(r#"@@check(name: "{name}", "{expression}")"#),
+            None => format!(r#"@@check("{expression}")"#),
+        }
+    }
+}
+
 impl<'a> ModelPair<'a> {
     /// The position of the model from the PSL, if existing. Used for
     /// sorting the models in the final introspected data model.
@@ -35,14 +89,39 @@ impl<'a> ModelPair<'a> {
     /// Name of the model in the PSL. The value can be sanitized if it
     /// contains characters that are not allowed in the PSL
     /// definition.
+    ///
+    /// If this model [`Self::uses_duplicate_name`] and the context's `NameCollisionStrategy` is
+    /// [`NameCollisionStrategy::PrefixWithNamespace`], the name is prefixed with [`Self::namespace`]
+    /// to make it unique instead of being left to collide.
     pub(crate) fn name(self) -> Cow<'a, str> {
-        self.context.table_prisma_name(self.next.id).prisma_name()
+        let name = self.context.table_prisma_name(self.next.id).prisma_name();
+
+        if self.uses_duplicate_name() && self.context.name_collision_strategy() == NameCollisionStrategy::PrefixWithNamespace
+        {
+            if let Some(namespace) = self.namespace() {
+                return Cow::Owned(format!("{namespace}_{name}"));
+            }
+        }
+
+        name
     }
 
     /// The mapped name, if defined, is the actual name of the model in
     /// the database.
+    ///
+    /// When [`Self::name`] prefixes the Prisma name to resolve a namespace collision, the original,
+    /// un-prefixed table name must still be the one Prisma talks to - so in that case this always
+    /// returns `Some`, forcing a `@@map` to the real table name even if none would otherwise be
+    /// needed.
     pub(crate) fn mapped_name(self) -> Option<&'a str> {
-        self.context.table_prisma_name(self.next.id).mapped_name()
+        let table_name = self.context.table_prisma_name(self.next.id);
+
+        if self.uses_duplicate_name() && self.context.name_collision_strategy() == NameCollisionStrategy::PrefixWithNamespace
+        {
+            return Some(table_name.mapped_name().unwrap_or_else(|| self.next.name()));
+        }
+
+        table_name.mapped_name()
     }
 
     /// True, if the name of the model is using a reserved identifier.
@@ -126,7 +205,8 @@ impl<'a> ModelPair<'a> {
     }
 
     /// True, if the model uses the same name as another top-level item from
-    /// a different namespace.
+    /// a different namespace. What happens as a result is up to the context's
+    /// `NameCollisionStrategy` - see [`Self::name`].
     pub(crate) fn uses_duplicate_name(self) -> bool {
         self.previous.is_none() && !self.context.name_is_unique(self.next.name())
     }
@@ -134,9 +214,15 @@ impl<'a> ModelPair<'a> {
     /// If the model is marked as ignored. Can happen either if user
     /// explicitly sets the model attribute, or if the model has no
     /// usable identifiers.
+    ///
+    /// A model whose primary key was promoted from a unique index by [`Self::id`] always counts as
+    /// having a usable identifier here, even on a connector/context combination where
+    /// `has_usable_identifier` might disagree, so that enabling the promotion never leaves a model
+    /// implicitly ignored.
     pub(crate) fn ignored(self) -> bool {
         let explicit_ignore = self.previous.map(|model| model.is_ignored()).unwrap_or(false);
-        let implicit_ignore = !self.has_usable_identifier() && self.scalar_fields().len() > 0;
+        let has_identifier = self.has_usable_identifier() || self.id().is_some();
+        let implicit_ignore = !has_identifier && self.scalar_fields().len() > 0;
 
         explicit_ignore || implicit_ignore
     }
@@ -145,13 +231,20 @@ impl<'a> ModelPair<'a> {
     /// specifically the ones defined in the model level, skipping the
     /// primary key and unique index defined in a field.
     ///
+    /// Also skips the index [`Self::promoted_unique_index_as_id`] chose to stand in for a missing
+    /// primary key: that index is rendered as `@@id`/`@id` instead, and would otherwise show up here
+    /// a second time as `@@unique`.
+    ///
     /// For the primary key, use [`ModelPair#id`]. For a field-level
     /// unique, use [`ScalarFieldPair#unique`].
     pub(crate) fn indexes(self) -> impl Iterator<Item = IndexPair<'a>> {
+        let promoted_id = self.promoted_unique_index().map(|idx| idx.id);
+
         self.next
             .indexes()
             .filter(|i| !(i.is_unique() && i.columns().len() == 1))
             .filter(|i| !i.is_primary_key())
+            .filter(move |i| Some(i.id) != promoted_id)
             .map(move |next| {
                 let previous = self.previous.and_then(|prev| {
                     prev.indexes().find(|idx| {
@@ -172,18 +265,77 @@ impl<'a> ModelPair<'a> {
             })
     }
 
-    /// The primary key of the model, if defined. It will only return
-    /// a value, if the field should be defined in a model as `@@id`:
-    /// e.g. when it holds more than one field.
-    pub(crate) fn id(self) -> Option<IdPair<'a>> {
+    /// Iterates over the table-level CHECK constraints of the model, so introspection can round-trip
+    /// them into a `@@check(...)` attribute (or, at minimum, preserve the raw expression as a
+    /// comment) instead of silently dropping them on re-introspection.
+    pub(crate) fn check_constraints(self) -> impl Iterator<Item = CheckConstraintPair<'a>> + 'a {
         self.next
-            .primary_key()
-            .filter(|pk| pk.columns().len() > 1)
-            .and_then(move |pk| {
+            .check_constraints()
+            .map(move |next| Pair::new(self.context, None, next))
+    }
+
+    /// Renders every table-level CHECK constraint of this model into the attribute (or, at minimum,
+    /// comment) text the PSL renderer should emit alongside its other `@@`-level attributes - see
+    /// [`CheckConstraintPair::render`] - so a constraint pulled off the database is never silently
+    /// dropped from the introspected schema.
+    pub(crate) fn check_constraint_attributes(self) -> Vec<String> {
+        self.check_constraints().map(CheckConstraintPair::render).collect()
+    }
+
+    /// The primary key of the model, if defined. It will only return a value if the field should be
+    /// defined in a model as `@@id`: e.g. when it holds more than one field, or when a qualifying
+    /// unique index was promoted to stand in for a missing primary key - see
+    /// [`Self::promoted_unique_index_as_id`].
+    pub(crate) fn id(self) -> Option<IdPair<'a>> {
+        match self.next.primary_key() {
+            Some(pk) if pk.columns().len() > 1 => {
                 let id = self.previous.and_then(|model| model.primary_key());
                 let pair = Pair::new(self.context, id, pk);
 
                 (!pair.defined_in_a_field()).then_some(pair)
+            }
+            Some(_) => None,
+            None => self.promoted_unique_index_as_id(),
+        }
+    }
+
+    /// When the table has no primary key at all, and the introspection context is configured to
+    /// infer one (`Context::infers_id_from_unique_index`), promotes the best qualifying
+    /// unique index - one whose columns are all required and supported, same as
+    /// [`Self::has_usable_identifier`] - to stand in for `@id`/`@@id`. The selection is deterministic:
+    /// the unique index with the fewest columns wins, ties broken by constraint name.
+    ///
+    /// Unlike the genuine-primary-key case handled in [`Self::id`], the `defined_in_a_field` check
+    /// doesn't apply here: that check exists to let a single-column primary key fall through to the
+    /// scalar field's own `@id` rendering, but a promoted index is never a real primary key, so no
+    /// field-level path will ever pick it up on its own. A single-column promotion is therefore
+    /// rendered as `@@id([column])` too, same as a multi-column one.
+    fn promoted_unique_index_as_id(self) -> Option<IdPair<'a>> {
+        let promoted = self.promoted_unique_index()?;
+        let id = self.previous.and_then(|model| model.primary_key());
+
+        Some(Pair::new(self.context, id, promoted))
+    }
+
+    /// The unique index, if any, that [`Self::promoted_unique_index_as_id`] would promote to stand in
+    /// for a missing primary key. Factored out so [`Self::indexes`] can exclude the very same index
+    /// from the `@@unique` list it builds, instead of emitting it twice.
+    fn promoted_unique_index(self) -> Option<sql::IndexWalker<'a>> {
+        if !self.context.infers_id_from_unique_index() {
+            return None;
+        }
+
+        self.next
+            .indexes()
+            .filter(|idx| idx.is_unique())
+            .filter(|idx| {
+                idx.columns().all(|c| {
+                    !matches!(
+                        c.as_column().column_type().family,
+                        sql::ColumnTypeFamily::Unsupported(_)
+                    ) && c.as_column().arity().is_required()
+                })
             })
+            .min_by_key(|idx| (idx.columns().len(), idx.name().to_owned()))
     }
 }
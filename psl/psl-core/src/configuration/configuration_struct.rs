@@ -14,22 +14,61 @@ pub struct Configuration {
 }
 
 impl Configuration {
+    /// At least one datasource must be defined, and datasource names must be unique across the
+    /// configuration so that `datasource_by_name` is unambiguous.
     pub fn validate_that_one_datasource_is_provided(&self) -> Result<(), Diagnostics> {
         if self.datasources.is_empty() {
-            Err(DatamodelError::new_validation_error(
-                "You defined no datasource. You must define exactly one datasource.",
+            return Err(DatamodelError::new_validation_error(
+                "You defined no datasource. You must define at least one datasource.",
                 schema_ast::ast::Span::new(0, 0),
             )
-            .into())
+            .into());
+        }
+
+        let mut diagnostics = Diagnostics::new();
+        let mut seen_names = std::collections::HashSet::new();
+
+        for datasource in &self.datasources {
+            if !seen_names.insert(datasource.name.as_str()) {
+                diagnostics.push_error(DatamodelError::new_source_validation_error(
+                    &format!(
+                        "Datasource \"{}\" is defined multiple times. Datasource names must be unique.",
+                        datasource.name
+                    ),
+                    &datasource.name,
+                    datasource.span,
+                ));
+            }
+        }
+
+        if diagnostics.has_errors() {
+            Err(diagnostics)
         } else {
             Ok(())
         }
     }
 
+    /// The datasource named `name`, if the configuration defines one. Useful for resolving the
+    /// active datasource by name in a multi-datasource configuration.
+    pub fn datasource_by_name(&self, name: &str) -> Option<&Datasource> {
+        self.datasources.iter().find(|source| source.name == name)
+    }
+
+    /// The relation mode of the first datasource, kept as a convenience for the common
+    /// single-datasource case. For a multi-datasource configuration, prefer
+    /// [`Self::relation_mode_for`].
     pub fn relation_mode(&self) -> Option<RelationMode> {
         self.datasources.first().map(|source| source.relation_mode())
     }
 
+    /// The relation mode of the datasource named `datasource_name`, if it exists.
+    pub fn relation_mode_for(&self, datasource_name: &str) -> Option<RelationMode> {
+        self.datasource_by_name(datasource_name).map(|source| source.relation_mode())
+    }
+
+    /// The maximum identifier length of the first datasource, kept as a convenience for the common
+    /// single-datasource case. For a multi-datasource configuration, prefer
+    /// [`Self::max_identifier_length_for`].
     pub fn max_identifier_length(&self) -> usize {
         self.datasources
             .first()
@@ -37,6 +76,14 @@ impl Configuration {
             .unwrap_or(usize::MAX)
     }
 
+    /// The maximum identifier length of the datasource named `datasource_name`, or `usize::MAX` if no
+    /// such datasource exists.
+    pub fn max_identifier_length_for(&self, datasource_name: &str) -> usize {
+        self.datasource_by_name(datasource_name)
+            .map(|source| source.active_connector.max_identifier_length())
+            .unwrap_or(usize::MAX)
+    }
+
     pub fn preview_features(&self) -> BitFlags<PreviewFeature> {
         self.generators.iter().fold(BitFlags::empty(), |acc, generator| {
             acc | generator.preview_features.unwrap_or_default()
@@ -0,0 +1,49 @@
+//! `@skip`/`@include`-style conditional selections, modeled after GraphQL's directives.
+//!
+//! A [`Directive`] lives on a [`super::SelectionTemplate`] and decides, at
+//! [`super::OperationTemplate::resolve_variables`] time, whether the nested selection it's attached
+//! to survives into the resolved [`super::Selection`] tree at all; directives on an operation's
+//! top-level selection are not evaluated. This lets a client send one operation
+//! shape whose effective shape is trimmed per request - without the query-graph builders downstream
+//! ever finding out that conditionals were involved, since by the time they see the `Operation` the
+//! tree has already been pruned. Two requests that prune down to the same effective shape still
+//! produce structurally identical operations, so they keep compacting together in a `BatchDocument`
+//! exactly as if the client had sent the trimmed shape directly.
+
+use super::{Pos, TemplateValue};
+
+/// A conditional directive attached to a [`super::SelectionTemplate`], whose condition may itself
+/// reference a variable. Carries the [`Pos`] of the directive in the original request document, if
+/// known, so a `FilterExtractionError` raised while resolving its condition can point back at it.
+#[derive(Debug, Clone)]
+pub enum Directive {
+    /// `@skip(if: <condition>)`: the selection is pruned when `condition` resolves to `true`.
+    Skip(TemplateValue, Option<Pos>),
+    /// `@include(if: <condition>)`: the selection is pruned when `condition` resolves to `false`.
+    Include(TemplateValue, Option<Pos>),
+}
+
+impl Directive {
+    pub(super) fn condition(&self) -> &TemplateValue {
+        match self {
+            Self::Skip(condition, _) => condition,
+            Self::Include(condition, _) => condition,
+        }
+    }
+
+    pub(super) fn pos(&self) -> Option<Pos> {
+        match self {
+            Self::Skip(_, pos) => *pos,
+            Self::Include(_, pos) => *pos,
+        }
+    }
+
+    /// Given the resolved boolean value of this directive's condition, whether the selection it's
+    /// attached to should survive.
+    pub(super) fn keeps(&self, condition_value: bool) -> bool {
+        match self {
+            Self::Skip(..) => !condition_value,
+            Self::Include(..) => condition_value,
+        }
+    }
+}
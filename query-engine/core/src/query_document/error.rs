@@ -0,0 +1,69 @@
+//! Errors produced while parsing and validating an incoming [`QueryDocument`](super::QueryDocument),
+//! or while resolving a parameterized operation against a concrete set of [`Variables`](super::Variables).
+
+use super::Pos;
+use std::fmt;
+use thiserror::Error;
+
+/// A parsing/validation failure, optionally tagged with the [`Pos`] of the IR node that caused it.
+///
+/// An error raised while resolving an argument value or `@skip`/`@include` condition of an
+/// [`OperationTemplate`](super::OperationTemplate) carries the position the `parser` module recorded
+/// for that value, so that callers can render a caret against the original request document instead
+/// of just a field/argument name. An `UnusedVariable` error carries no position.
+#[derive(Debug)]
+pub struct QueryParserError {
+    pub kind: QueryParserErrorKind,
+    pub pos: Option<Pos>,
+}
+
+impl QueryParserError {
+    pub fn new(kind: QueryParserErrorKind, pos: Option<Pos>) -> Self {
+        Self { kind, pos }
+    }
+}
+
+impl fmt::Display for QueryParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.pos {
+            Some(pos) => write!(f, "{} (at {pos})", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for QueryParserError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.kind)
+    }
+}
+
+impl From<QueryParserErrorKind> for QueryParserError {
+    fn from(kind: QueryParserErrorKind) -> Self {
+        Self { kind, pos: None }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum QueryParserErrorKind {
+    #[error("Variable '${0}' is used in the operation but was never declared.")]
+    UndeclaredVariable(String),
+
+    #[error("Variable '${0}' is declared on the operation but never used.")]
+    UnusedVariable(String),
+
+    #[error("No value was provided for variable '${0}'.")]
+    MissingVariable(String),
+
+    #[error("Variable '${name}' is declared as '{expected}', but the provided value is not compatible with it.")]
+    VariableTypeMismatch { name: String, expected: String },
+
+    #[error("Unknown field '{field}' on '{container}'.")]
+    FieldNotFound { field: String, container: String },
+
+    #[error("Unknown argument '{argument}' for field '{field}'.")]
+    ArgumentNotFound { argument: String, field: String },
+
+    #[error("Failed to build a filter from the provided argument: {0}")]
+    FilterExtractionError(String),
+}
@@ -0,0 +1,73 @@
+//! Read-only look-ahead over the selection tree, inspired by async-graphql's `Lookahead`.
+//!
+//! Query-graph builders (e.g. `nested_update`, `insert_find_children_by_parent_node`) and connectors
+//! currently have to walk the selection set themselves, repeatedly, to find out which nested fields a
+//! client actually asked for. [`Lookahead`] gives them a single, read-only cursor over a
+//! [`Selection`] they can inspect up front, so a builder can for instance skip projecting a relation's
+//! identifiers entirely if the relation's own fields are never selected back, or a connector that
+//! supports joins can decide to pre-plan one from a single inspection of the selection set.
+
+use super::{Operation, Selection};
+use prisma_models::{ModelRef, RelationFieldRef};
+
+/// A read-only cursor over a single [`Selection`] and its nested selections.
+#[derive(Debug, Clone, Copy)]
+pub struct Lookahead<'a> {
+    selection: &'a Selection,
+}
+
+impl<'a> Lookahead<'a> {
+    fn new(selection: &'a Selection) -> Self {
+        Self { selection }
+    }
+
+    /// True if `field_name` is selected at this level.
+    pub fn selects(&self, field_name: &str) -> bool {
+        self.selection.contains_nested_selection(field_name)
+    }
+
+    /// A look-ahead on the nested selection named `field_name`, if it is selected.
+    pub fn nested(&self, field_name: &str) -> Option<Lookahead<'a>> {
+        self.selection
+            .nested_selections()
+            .iter()
+            .find(|selection| selection.name() == field_name)
+            .map(Lookahead::new)
+    }
+
+    /// All nested selections at this level, each as its own look-ahead cursor.
+    pub fn iter(&self) -> impl Iterator<Item = Lookahead<'a>> {
+        self.selection.nested_selections().iter().map(Lookahead::new)
+    }
+
+    /// The relation fields of `model` that are selected at this level. Lets a query-graph builder
+    /// decide, from a single inspection, whether a nested relation needs to be read at all.
+    pub fn required_relations(&self, model: &'a ModelRef) -> impl Iterator<Item = RelationFieldRef> + 'a {
+        let names: Vec<&str> = self.selection.nested_selections().iter().map(Selection::name).collect();
+
+        model
+            .fields()
+            .relation()
+            .filter(move |rf| names.contains(&rf.name.as_str()))
+    }
+}
+
+impl Selection {
+    /// Returns a read-only [`Lookahead`] cursor over this selection's nested selections.
+    pub fn look_ahead(&self) -> Lookahead<'_> {
+        Lookahead::new(self)
+    }
+}
+
+impl Operation {
+    /// Returns a read-only [`Lookahead`] cursor over the top-level selection of this operation,
+    /// whether it's a read or a write.
+    pub fn look_ahead(&self) -> Lookahead<'_> {
+        let selection = match self {
+            Self::Read(selection) => selection,
+            Self::Write(selection) => selection,
+        };
+
+        Lookahead::new(selection)
+    }
+}
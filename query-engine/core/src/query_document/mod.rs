@@ -14,19 +14,36 @@
 //!    - it can be aliased,
 //!    - it can have a number of nested selections (selection set in GQL).
 //! - Arguments contain concrete values and complex subtypes that are parsed and validated by the query builders, and then used for querying data (input types in GQL).
+//! - A parsed operation can also be kept as an [`OperationTemplate`], whose arguments may reference
+//!   named variables instead of concrete values; [`OperationTemplate::resolve_variables`] turns such
+//!   a template, plus a [`Variables`] payload, into an ordinary `Operation`.
+//! - Parsed nodes can optionally be wrapped in [`Positioned<T>`] to retain the [`Pos`] they came from
+//!   in the original request document, which `QueryParserError` surfaces for better diagnostics.
+//! - `Operation::look_ahead`/`Selection::look_ahead` give query-graph builders and connectors a
+//!   read-only cursor over the selection tree, to plan joins or skip reads before doing any work.
+//! - A [`SelectionTemplate`] can carry `@skip`/`@include` [`Directive`]s; resolving variables prunes
+//!   the selection tree according to them before the rest of the engine ever sees the `Operation`.
+mod directives;
 mod error;
+mod look_ahead;
 mod operation;
 mod parse_ast;
 mod parser;
+mod pos;
 mod selection;
 mod transformers;
+mod variables;
 
+pub use directives::*;
 pub use error::*;
+pub use look_ahead::*;
 pub use operation::*;
 pub use parse_ast::*;
 pub use parser::*;
+pub use pos::*;
 pub use selection::*;
 pub use transformers::*;
+pub use variables::*;
 
 use crate::resolve_compound_field;
 use prisma_models::{ModelRef, PrismaValue};
@@ -93,6 +110,11 @@ impl BatchDocument {
     }
 
     /// Checks whether a BatchDocument can be compacted.
+    ///
+    /// Note that the operations don't need identical nested selection sets to compact: the
+    /// `findMany` built by `CompactedDocument::from_operations` selects the union of every
+    /// operation's selection set, and each response row is projected back down to whatever its
+    /// originating `findUnique` actually asked for via [`CompactedDocument::project_row`].
     fn can_compact(&self, schema: &QuerySchemaRef) -> bool {
         match self {
             Self::Multi(operations, _) => match operations.split_first() {
@@ -106,15 +128,7 @@ impl BatchDocument {
                         return false;
                     }
 
-                    rest.iter().all(|op| {
-                        op.is_find_unique(schema)
-                            && first.name() == op.name()
-                            && first.nested_selections().len() == op.nested_selections().len()
-                            && first
-                                .nested_selections()
-                                .iter()
-                                .all(|fop| op.nested_selections().contains(fop))
-                    })
+                    rest.iter().all(|op| op.is_find_unique(schema) && first.name() == op.name())
                 }
                 _ => false,
             },
@@ -156,7 +170,11 @@ impl BatchDocumentTransaction {
 #[derive(Debug, Clone)]
 pub struct CompactedDocument {
     pub arguments: Vec<HashMap<String, PrismaValue>>,
-    pub nested_selection: Vec<String>,
+    /// The nested selection tree each original operation asked for, in `arguments`/`keys` order. The
+    /// `findMany` built for this document selects the union of all of these (see `from_operations`),
+    /// so a response row must be projected back down to `requested_selections[i]` via
+    /// [`Self::project_row`] before it's handed back for request `i`.
+    requested_selections: Vec<Vec<Selection>>,
     pub operation: Operation,
     pub keys: Vec<String>,
     name: String,
@@ -171,6 +189,40 @@ impl CompactedDocument {
         format!("findMany{}", self.name)
     }
 
+    /// Projects a single response row of the compacted `findMany` - which was read against the union
+    /// of every original operation's selection set - down to exactly what the operation at `index`
+    /// (in `arguments`/`keys` order) asked for.
+    ///
+    /// Fields are matched by response key (alias if the original selection had one, its name
+    /// otherwise), so an aliased field isn't mistaken for absent just because its name differs from
+    /// the key the findMany selected it under. The projection also recurses into nested
+    /// object/list values, so that a sibling operation's extra *nested* selection - not just an extra
+    /// top-level one - never leaks into this operation's result.
+    pub fn project_row(&self, index: usize, row: Vec<(String, PrismaValue)>) -> Vec<(String, PrismaValue)> {
+        Self::project(&self.requested_selections[index], row)
+    }
+
+    fn project(wanted: &[Selection], row: Vec<(String, PrismaValue)>) -> Vec<(String, PrismaValue)> {
+        row.into_iter()
+            .filter_map(|(field, value)| {
+                let selection = wanted.iter().find(|s| response_key(s) == field)?;
+                Some((field, Self::project_value(selection, value)))
+            })
+            .collect()
+    }
+
+    fn project_value(selection: &Selection, value: PrismaValue) -> PrismaValue {
+        let nested = selection.nested_selections();
+
+        match value {
+            PrismaValue::Object(fields) if !nested.is_empty() => PrismaValue::Object(Self::project(nested, fields)),
+            PrismaValue::List(items) if !nested.is_empty() => {
+                PrismaValue::List(items.into_iter().map(|item| Self::project_value(selection, item)).collect())
+            }
+            other => other,
+        }
+    }
+
     /// Here be the dragons. Ay caramba!
     pub fn from_operations(ops: Vec<Operation>, schema: &QuerySchemaRef) -> Self {
         let field = schema.find_query_field(ops.first().unwrap().name()).unwrap();
@@ -189,10 +241,28 @@ impl CompactedDocument {
             // same. Otherwise we fail hard here.
             let mut builder = Selection::with_name(selections[0].name().replacen("findUnique", "findMany", 1));
 
-            // Take the nested selection set from the first query. We took care
-            // earlier that all the nested selections are the same in every
-            // query. Otherwise we fail hard here.
-            builder.set_nested_selections(selections[0].nested_selections().to_vec());
+            // The nested selection sets of the original queries may differ, so the findMany
+            // selects the union of all of them (first-seen order). Two sibling queries selecting the
+            // same field under different nested selections (e.g. two different `author { ... }`
+            // selections) are merged into one, recursively, rather than both being kept as duplicate
+            // top-level entries. Each response row is later projected back down to whatever its
+            // originating query actually asked for, using the per-operation `requested_selections` we
+            // capture below.
+            let union_nested_selections = selections.iter().fold(Vec::new(), |mut acc: Vec<Selection>, selection| {
+                for nested in selection.nested_selections() {
+                    match acc.iter().position(|existing| response_key(existing) == response_key(nested)) {
+                        Some(pos) => {
+                            let merged = merge_selections(acc[pos].clone(), nested);
+                            acc[pos] = merged;
+                        }
+                        None => acc.push(nested.clone()),
+                    }
+                }
+
+                acc
+            });
+
+            builder.set_nested_selections(union_nested_selections);
 
             // The query arguments are extracted here. Combine all query
             // arguments from the different queries into a one large argument.
@@ -229,13 +299,12 @@ impl CompactedDocument {
             builder
         };
 
-        // We want to store the original nested selections so we can filter out
-        // the added unique selections from the responses if the original
-        // selection set didn't have them.
-        let nested_selection = selections[0]
-            .nested_selections()
+        // We want to store each original query's own nested selection tree so we can project every
+        // response row down to exactly what that query asked for, dropping both the unique fields
+        // we added above for matching and any field (nested or not) only a sibling query selected.
+        let requested_selections: Vec<Vec<Selection>> = selections
             .iter()
-            .map(|s| s.name().to_string())
+            .map(|selection| selection.nested_selections().to_vec())
             .collect();
 
         // Saving the stub of the query name for later use.
@@ -265,13 +334,40 @@ impl CompactedDocument {
         Self {
             name,
             arguments,
-            nested_selection,
+            requested_selections,
             keys,
             operation: Operation::Read(selection),
         }
     }
 }
 
+/// The key a selection's value is reported under in a response row: its alias if it has one, its
+/// name otherwise.
+fn response_key(selection: &Selection) -> &str {
+    selection.alias().as_deref().unwrap_or_else(|| selection.name())
+}
+
+/// Merges `incoming`'s nested selections into `existing`'s, recursively: a nested selection present
+/// on both sides (matched by [`response_key`]) is itself merged rather than duplicated, so a field
+/// selected with different sub-selections by two sibling operations ends up with the union of both.
+fn merge_selections(existing: Selection, incoming: &Selection) -> Selection {
+    let mut children = existing.nested_selections().to_vec();
+
+    for incoming_child in incoming.nested_selections() {
+        match children.iter().position(|child| response_key(child) == response_key(incoming_child)) {
+            Some(pos) => {
+                let merged_child = merge_selections(children.remove(pos), incoming_child);
+                children.push(merged_child);
+            }
+            None => children.push(incoming_child.clone()),
+        }
+    }
+
+    let mut merged = existing;
+    merged.set_nested_selections(children);
+    merged
+}
+
 /// Takes in a unique filter, extract the scalar filters and return a simple list of field/filter.
 /// This list is used to build a findMany query from multiple findUnique queries.
 /// Therefore, compound unique filters are walked and each individual field is added. eg:
@@ -304,3 +400,122 @@ fn extract_filter(where_obj: Vec<SelectionArgument>, model: &ModelRef) -> Vec<Se
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> Selection {
+        Selection::with_name(name)
+    }
+
+    fn aliased_field(name: &str, alias: &str) -> Selection {
+        let mut selection = Selection::with_name(name);
+        selection.set_alias(Some(alias.to_owned()));
+        selection
+    }
+
+    fn nested_field(name: &str, nested: Vec<Selection>) -> Selection {
+        let mut selection = Selection::with_name(name);
+        selection.set_nested_selections(nested);
+        selection
+    }
+
+    fn compacted_document(requested_selections: Vec<Vec<Selection>>) -> CompactedDocument {
+        CompactedDocument {
+            name: "Post".to_owned(),
+            arguments: requested_selections.iter().map(|_| HashMap::new()).collect(),
+            keys: vec!["id".to_owned()],
+            operation: Operation::Read(Selection::with_name("findManyPost")),
+            requested_selections,
+        }
+    }
+
+    #[test]
+    fn project_row_drops_fields_the_request_did_not_select() {
+        let doc = compacted_document(vec![
+            vec![field("id"), field("title")],
+            vec![field("id"), field("author")],
+        ]);
+
+        let row = vec![
+            ("id".to_owned(), PrismaValue::Int(1)),
+            ("title".to_owned(), PrismaValue::String("Hello".to_owned())),
+            ("author".to_owned(), PrismaValue::String("Alice".to_owned())),
+        ];
+
+        assert_eq!(
+            doc.project_row(0, row.clone()),
+            vec![
+                ("id".to_owned(), PrismaValue::Int(1)),
+                ("title".to_owned(), PrismaValue::String("Hello".to_owned())),
+            ]
+        );
+
+        assert_eq!(
+            doc.project_row(1, row),
+            vec![
+                ("id".to_owned(), PrismaValue::Int(1)),
+                ("author".to_owned(), PrismaValue::String("Alice".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn project_row_drops_the_unique_matching_fields_added_for_compaction() {
+        // `from_operations` adds the unique filter's own fields to the findMany selection so
+        // responses can be matched back to requests; a request that didn't select them itself
+        // shouldn't see them in its projected row.
+        let doc = compacted_document(vec![vec![field("title")]]);
+
+        let row = vec![
+            ("id".to_owned(), PrismaValue::Int(1)),
+            ("title".to_owned(), PrismaValue::String("Hello".to_owned())),
+        ];
+
+        assert_eq!(doc.project_row(0, row), vec![("title".to_owned(), PrismaValue::String("Hello".to_owned()))]);
+    }
+
+    #[test]
+    fn project_row_keeps_an_aliased_field_matched_by_its_response_key() {
+        let doc = compacted_document(vec![vec![aliased_field("title", "foo")]]);
+
+        let row = vec![("foo".to_owned(), PrismaValue::String("Hello".to_owned()))];
+
+        assert_eq!(doc.project_row(0, row), vec![("foo".to_owned(), PrismaValue::String("Hello".to_owned()))]);
+    }
+
+    #[test]
+    fn project_row_recurses_into_nested_selections() {
+        // Sibling request 1 wants `author { name }`, sibling request 0 only wants `author { id }` -
+        // the findMany selects the union, `author { id name }`, so request 0's projected row must
+        // drop `name` from the nested object instead of leaking it.
+        let doc = compacted_document(vec![vec![nested_field("author", vec![field("id")])]]);
+
+        let row = vec![(
+            "author".to_owned(),
+            PrismaValue::Object(vec![
+                ("id".to_owned(), PrismaValue::Int(1)),
+                ("name".to_owned(), PrismaValue::String("Alice".to_owned())),
+            ]),
+        )];
+
+        assert_eq!(
+            doc.project_row(0, row),
+            vec![("author".to_owned(), PrismaValue::Object(vec![("id".to_owned(), PrismaValue::Int(1))]))]
+        );
+    }
+
+    #[test]
+    fn merge_selections_unions_differently_nested_sibling_selections() {
+        let merged = merge_selections(
+            nested_field("author", vec![field("id")]),
+            &nested_field("author", vec![field("name")]),
+        );
+
+        let mut nested: Vec<&str> = merged.nested_selections().iter().map(|s| response_key(s)).collect();
+        nested.sort();
+
+        assert_eq!(nested, vec!["id", "name"]);
+    }
+}
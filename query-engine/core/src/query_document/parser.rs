@@ -0,0 +1,214 @@
+//! Turns a parsed wire-level request into the query-document IR.
+//!
+//! The wire format itself - whatever the protocol layer (GraphQL, JSON-RPC, ...) actually speaks -
+//! isn't part of the slice of the query engine checked into this source tree; [`WireDocument`] and
+//! friends below are the minimal shape this module needs from it: a tree of named, aliasable
+//! selections with `key: value` arguments and `@skip`/`@include` directives, each tagged with the
+//! [`Pos`] it came from. A real protocol adapter converts its own AST into this shape before calling
+//! [`parse_document`].
+//!
+//! This is the one place that constructs an [`OperationTemplate`]: it wraps every argument value and
+//! directive condition in a [`TemplateValue`], carrying along the [`Pos`] of the wire node it was
+//! parsed from, and wraps the document itself in a [`Positioned`] so a caller can point a
+//! `QueryParserError` raised anywhere downstream back at where the request started.
+
+use super::{
+    Directive, OperationTemplate, Pos, Positioned, QueryParserResult, SelectionTemplate, TemplateValue,
+    VariableDefinitions, VariableType,
+};
+use prisma_models::PrismaValue;
+
+/// A parsed wire-level request, not yet resolved against concrete [`super::Variables`].
+#[derive(Debug, Clone)]
+pub struct WireDocument {
+    pub operations: Vec<Positioned<WireOperation>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WireOperation {
+    pub is_write: bool,
+    pub selection: WireSelection,
+    pub variable_definitions: Vec<(String, VariableType)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WireSelection {
+    pub name: String,
+    pub alias: Option<String>,
+    pub arguments: Vec<(String, Positioned<WireValue>)>,
+    pub directives: Vec<WireDirective>,
+    pub nested_selections: Vec<WireSelection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WireDirective {
+    pub kind: WireDirectiveKind,
+    pub condition: Positioned<WireValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireDirectiveKind {
+    Skip,
+    Include,
+}
+
+/// An argument/condition value as it comes off the wire: either a literal, or a reference to a
+/// variable that's only resolved once [`OperationTemplate::resolve_variables`] runs.
+#[derive(Debug, Clone)]
+pub enum WireValue {
+    Value(PrismaValue),
+    Variable(String),
+}
+
+/// Parses a [`WireDocument`] into an [`OperationTemplate`], ready to be resolved against a concrete
+/// [`super::Variables`] payload per replay, wrapped in the [`Pos`] of the document itself.
+///
+/// Only handles a single operation today: batching multiple wire operations into a
+/// [`super::BatchDocument`] happens above the template layer, once each one has been parsed and
+/// resolved into a plain [`super::Operation`].
+pub fn parse_document(document: Positioned<WireDocument>) -> QueryParserResult<Positioned<OperationTemplate>> {
+    let pos = document.pos();
+    let operation = document
+        .into_inner()
+        .operations
+        .into_iter()
+        .next()
+        .expect("a parsed wire document always has at least one operation");
+
+    let template = parse_operation(operation.into_inner())?;
+
+    Ok(Positioned::new(template, pos))
+}
+
+fn parse_operation(operation: WireOperation) -> QueryParserResult<OperationTemplate> {
+    let selection = parse_selection(operation.selection);
+
+    let mut definitions = VariableDefinitions::new();
+    for (name, r#type) in operation.variable_definitions {
+        definitions.insert(name, r#type);
+    }
+
+    Ok(if operation.is_write {
+        OperationTemplate::Write(selection, definitions)
+    } else {
+        OperationTemplate::Read(selection, definitions)
+    })
+}
+
+fn parse_selection(wire: WireSelection) -> SelectionTemplate {
+    let mut selection = SelectionTemplate::with_name(wire.name);
+    selection.set_alias(wire.alias);
+
+    for (key, value) in wire.arguments {
+        let pos = value.pos();
+        selection.push_argument(key, parse_value(value.into_inner()), pos);
+    }
+
+    for directive in wire.directives {
+        let pos = directive.condition.pos();
+        let condition = parse_value(directive.condition.into_inner());
+
+        selection.push_directive(match directive.kind {
+            WireDirectiveKind::Skip => Directive::Skip(condition, pos),
+            WireDirectiveKind::Include => Directive::Include(condition, pos),
+        });
+    }
+
+    for nested in wire.nested_selections {
+        selection.push_nested_selection(parse_selection(nested));
+    }
+
+    selection
+}
+
+fn parse_value(value: WireValue) -> TemplateValue {
+    match value {
+        WireValue::Value(value) => TemplateValue::Value(value),
+        WireValue::Variable(name) => TemplateValue::Variable(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: usize) -> Pos {
+        Pos { line, column: 1, offset: 0 }
+    }
+
+    #[test]
+    fn parse_document_substitutes_the_wire_variable_through_to_a_resolved_operation() {
+        let mut selection = WireSelection {
+            name: "findOnePost".to_owned(),
+            ..Default::default()
+        };
+        selection
+            .arguments
+            .push(("id".to_owned(), Positioned::new(WireValue::Variable("id".to_owned()), Some(pos(3)))));
+
+        let document = Positioned::new(
+            WireDocument {
+                operations: vec![Positioned::new(
+                    WireOperation {
+                        is_write: false,
+                        selection,
+                        variable_definitions: vec![("id".to_owned(), VariableType::Int)],
+                    },
+                    Some(pos(1)),
+                )],
+            },
+            Some(pos(1)),
+        );
+
+        let template = parse_document(document).unwrap();
+        assert_eq!(template.pos(), Some(pos(1)));
+
+        let mut variables = super::super::Variables::new();
+        variables.insert("id", PrismaValue::Int(1));
+
+        let operation = template.into_inner().resolve_variables(&variables).unwrap();
+        let selection = operation.into_read().unwrap();
+
+        assert_eq!(selection.arguments()[0], ("id".to_owned(), PrismaValue::Int(1)));
+    }
+
+    #[test]
+    fn parse_document_wires_a_skip_directive_so_the_selection_is_pruned() {
+        let mut root = WireSelection {
+            name: "findOnePost".to_owned(),
+            ..Default::default()
+        };
+
+        root.nested_selections.push(WireSelection {
+            name: "comments".to_owned(),
+            directives: vec![WireDirective {
+                kind: WireDirectiveKind::Skip,
+                condition: Positioned::new(WireValue::Variable("shouldSkip".to_owned()), Some(pos(2))),
+            }],
+            ..Default::default()
+        });
+
+        let document = Positioned::new(
+            WireDocument {
+                operations: vec![Positioned::new(
+                    WireOperation {
+                        is_write: false,
+                        selection: root,
+                        variable_definitions: vec![("shouldSkip".to_owned(), VariableType::Boolean)],
+                    },
+                    Some(pos(1)),
+                )],
+            },
+            Some(pos(1)),
+        );
+
+        let mut variables = super::super::Variables::new();
+        variables.insert("shouldSkip", PrismaValue::Boolean(true));
+
+        let template = parse_document(document).unwrap();
+        let operation = template.into_inner().resolve_variables(&variables).unwrap();
+        let selection = operation.into_read().unwrap();
+
+        assert!(!selection.contains_nested_selection("comments"));
+    }
+}
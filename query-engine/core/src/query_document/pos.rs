@@ -0,0 +1,96 @@
+//! Source positions for nodes of the query-document IR.
+//!
+//! Borrowed from async-graphql's `Pos`/`Positioned<T>`: as the `parser` module consumes the incoming
+//! wire document, it records the [`Pos`] of every argument value and `@skip`/`@include` condition on
+//! the [`SelectionTemplate`](super::SelectionTemplate) it builds, and returns the parsed
+//! [`OperationTemplate`](super::OperationTemplate) wrapped in a [`Positioned<T>`] carrying the
+//! position of the document itself. A [`QueryParserError`](super::QueryParserError) raised while
+//! resolving one of those arguments or conditions carries its position, so it can point back at the
+//! byte offset / line / column the offending value came from, instead of only naming it. Selections
+//! themselves carry no position, and neither does the resolved [`Operation`](super::Operation) /
+//! [`Selection`](super::Selection) / [`SelectionArgument`](super::SelectionArgument) tree that
+//! `dedup_selections` and `dedup_operations` work on.
+//!
+//! [`Positioned<T>`] derefs to `T` and its `PartialEq`/`Eq`/`Hash` impls ignore the position entirely,
+//! so wrapping a value doesn't change how it compares or hashes.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+
+/// A location in the original request document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Wraps a parsed IR node together with the [`Pos`] it originated from, if known.
+///
+/// Equality and hashing are entirely delegated to the wrapped value: two
+/// `Positioned<T>`s compare equal whenever their inner `T`s do, regardless of where in the document
+/// each one was parsed from.
+#[derive(Debug, Clone, Copy)]
+pub struct Positioned<T> {
+    pub pos: Option<Pos>,
+    value: T,
+}
+
+impl<T> Positioned<T> {
+    pub fn new(value: T, pos: Option<Pos>) -> Self {
+        Self { value, pos }
+    }
+
+    pub fn unpositioned(value: T) -> Self {
+        Self { value, pos: None }
+    }
+
+    pub fn pos(&self) -> Option<Pos> {
+        self.pos
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Positioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Positioned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Positioned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Positioned<T> {}
+
+impl<T: Hash> Hash for Positioned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> From<T> for Positioned<T> {
+    fn from(value: T) -> Self {
+        Self::unpositioned(value)
+    }
+}
@@ -0,0 +1,398 @@
+//! GraphQL-style named variables for the query-document IR.
+//!
+//! The IR in this module is protocol-agnostic and, outside of this file, only ever deals in
+//! already-resolved [`PrismaValue`]s. [`OperationTemplate`] is the one exception: it is built by the
+//! `parser` module from a wire document whose selection arguments may reference `$variable`
+//! placeholders instead of concrete values, alongside the variable's declared type. Calling
+//! [`OperationTemplate::resolve_variables`] with a concrete [`Variables`] payload substitutes every
+//! placeholder and produces an ordinary [`Operation`], so the rest of the engine never has to know
+//! that variables exist. This lets a client parse and validate an operation once, then replay it
+//! against many different variable payloads - and since the resulting templates are structurally
+//! identical across replays, it also makes otherwise-identical operations in a
+//! [`BatchDocument`](super::BatchDocument) far easier to detect and compact.
+
+use super::{Directive, Operation, Pos, QueryParserError, QueryParserErrorKind, QueryParserResult, Selection};
+use prisma_models::PrismaValue;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A concrete set of values supplied for the variables declared on an [`OperationTemplate`].
+///
+/// Mirrors async-graphql's `Variables(BTreeMap<Name, Value>)`: a simple, ordered map from variable
+/// name (without the leading `$`) to its value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables(BTreeMap<String, PrismaValue>);
+
+impl Variables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: PrismaValue) {
+        self.0.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PrismaValue> {
+        self.0.get(name)
+    }
+}
+
+impl FromIterator<(String, PrismaValue)> for Variables {
+    fn from_iter<T: IntoIterator<Item = (String, PrismaValue)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// The declared type of a variable, used to type-check the value a caller provides in
+/// [`OperationTemplate::resolve_variables`]. Intentionally mirrors the shape of PSL/GraphQL scalar
+/// and list types rather than the full input type system, since that's all a wire-level variable
+/// declaration can express.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableType {
+    String,
+    Int,
+    Float,
+    Boolean,
+    Json,
+    List(Box<VariableType>),
+    Nullable(Box<VariableType>),
+}
+
+impl VariableType {
+    /// True if `value` is compatible with this declared type.
+    fn accepts(&self, value: &PrismaValue) -> bool {
+        match (self, value) {
+            (Self::Nullable(_), PrismaValue::Null) => true,
+            (Self::Nullable(inner), value) => inner.accepts(value),
+            (Self::String, PrismaValue::String(_) | PrismaValue::Enum(_)) => true,
+            (Self::Int, PrismaValue::Int(_) | PrismaValue::BigInt(_)) => true,
+            (Self::Float, PrismaValue::Float(_) | PrismaValue::Int(_)) => true,
+            (Self::Boolean, PrismaValue::Boolean(_)) => true,
+            (Self::Json, PrismaValue::Json(_)) => true,
+            (Self::List(inner), PrismaValue::List(values)) => values.iter().all(|v| inner.accepts(v)),
+            _ => false,
+        }
+    }
+}
+
+/// The variables an [`OperationTemplate`] declares, keyed by name (without the leading `$`).
+#[derive(Debug, Clone, Default)]
+pub struct VariableDefinitions(BTreeMap<String, VariableType>);
+
+impl VariableDefinitions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, r#type: VariableType) {
+        self.0.insert(name.into(), r#type);
+    }
+
+    fn get(&self, name: &str) -> Option<&VariableType> {
+        self.0.get(name)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+
+/// A selection argument value as produced by the parser before variable resolution: either an
+/// already-concrete value, or a placeholder referencing a declared variable by name.
+#[derive(Debug, Clone)]
+pub enum TemplateValue {
+    Value(PrismaValue),
+    Variable(String),
+}
+
+/// A single `key: value` pair of a [`SelectionTemplate`], mirroring [`super::SelectionArgument`] but
+/// with a [`TemplateValue`] instead of an already-resolved [`PrismaValue`]. The position is the
+/// location of the value in the original request document, if known, and is used to tag any
+/// [`QueryParserError`] raised while resolving this argument's variable.
+pub type TemplateArgument = (String, TemplateValue, Option<Pos>);
+
+/// The unresolved counterpart of [`Selection`]: a selection whose arguments may still contain
+/// `$variable` placeholders, and which may be pruned entirely by its `@skip`/`@include`
+/// [`Directive`]s once those are resolved.
+#[derive(Debug, Clone, Default)]
+pub struct SelectionTemplate {
+    name: String,
+    alias: Option<String>,
+    arguments: Vec<TemplateArgument>,
+    directives: Vec<Directive>,
+    nested_selections: Vec<SelectionTemplate>,
+}
+
+impl SelectionTemplate {
+    pub fn with_name(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_alias(&mut self, alias: Option<String>) {
+        self.alias = alias;
+    }
+
+    pub fn push_argument(&mut self, key: impl Into<String>, value: TemplateValue, pos: Option<Pos>) {
+        self.arguments.push((key.into(), value, pos));
+    }
+
+    pub fn push_directive(&mut self, directive: Directive) {
+        self.directives.push(directive);
+    }
+
+    pub fn push_nested_selection(&mut self, selection: SelectionTemplate) {
+        self.nested_selections.push(selection);
+    }
+
+    /// Resolves this selection's `@skip`/`@include` directives and reports whether it should survive
+    /// into the pruned tree. A selection with no directives is always kept.
+    fn is_kept(&self, definitions: &VariableDefinitions, variables: &Variables) -> QueryParserResult<bool> {
+        for directive in &self.directives {
+            let condition = resolve_value(directive.condition(), directive.pos(), definitions, variables)?;
+
+            let condition = match condition {
+                PrismaValue::Boolean(b) => b,
+                _ => {
+                    return Err(QueryParserError::new(
+                        QueryParserErrorKind::FilterExtractionError(
+                            "`@skip`/`@include` condition must resolve to a boolean".to_owned(),
+                        ),
+                        directive.pos(),
+                    ))
+                }
+            };
+
+            if !directive.keeps(condition) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Substitutes every `$variable` placeholder in this template (and its nested selections) with
+    /// its value from `variables`, and drops any nested selection whose `@skip`/`@include` directives
+    /// prune it.
+    fn resolve(&self, definitions: &VariableDefinitions, variables: &Variables) -> QueryParserResult<Selection> {
+        let mut selection = Selection::with_name(self.name.clone());
+        selection.set_alias(self.alias.clone());
+
+        for (key, value, pos) in &self.arguments {
+            let resolved = resolve_value(value, *pos, definitions, variables)?;
+
+            selection.push_argument(key.clone(), resolved);
+        }
+
+        for nested in &self.nested_selections {
+            if !nested.is_kept(definitions, variables)? {
+                continue;
+            }
+
+            selection.push_nested_selection(nested.resolve(definitions, variables)?);
+        }
+
+        Ok(selection)
+    }
+
+    /// Collects the name of every variable referenced anywhere in this selection - its own
+    /// arguments, its directives' conditions, and recursively its nested selections - regardless of
+    /// whether a nested selection ends up pruned by `@skip`/`@include`.
+    ///
+    /// GraphQL determines variable usage statically from the document's shape, before directives are
+    /// evaluated, so [`OperationTemplate::resolve_variables`] checks declared variables against this
+    /// set rather than only the ones left standing after pruning.
+    fn referenced_variables(&self, names: &mut BTreeSet<String>) {
+        for (_, value, _) in &self.arguments {
+            if let TemplateValue::Variable(name) = value {
+                names.insert(name.clone());
+            }
+        }
+
+        for directive in &self.directives {
+            if let TemplateValue::Variable(name) = directive.condition() {
+                names.insert(name.clone());
+            }
+        }
+
+        for nested in &self.nested_selections {
+            nested.referenced_variables(names);
+        }
+    }
+}
+
+/// Resolves a single [`TemplateValue`] against `variables`.
+fn resolve_value(
+    value: &TemplateValue,
+    pos: Option<Pos>,
+    definitions: &VariableDefinitions,
+    variables: &Variables,
+) -> QueryParserResult<PrismaValue> {
+    match value {
+        TemplateValue::Value(value) => Ok(value.clone()),
+        TemplateValue::Variable(name) => {
+            let r#type = definitions
+                .get(name)
+                .ok_or_else(|| QueryParserError::new(QueryParserErrorKind::UndeclaredVariable(name.clone()), pos))?;
+
+            let value = variables
+                .get(name)
+                .ok_or_else(|| QueryParserError::new(QueryParserErrorKind::MissingVariable(name.clone()), pos))?;
+
+            if !r#type.accepts(value) {
+                return Err(QueryParserError::new(
+                    QueryParserErrorKind::VariableTypeMismatch {
+                        name: name.clone(),
+                        expected: format!("{type:?}"),
+                    },
+                    pos,
+                ));
+            }
+
+            Ok(value.clone())
+        }
+    }
+}
+
+/// The unresolved counterpart of [`Operation`]: an operation template whose selection arguments may
+/// reference variables declared in its [`VariableDefinitions`], built once by the parser and then
+/// replayed against many different [`Variables`] payloads via [`Self::resolve_variables`].
+#[derive(Debug, Clone)]
+pub enum OperationTemplate {
+    Read(SelectionTemplate, VariableDefinitions),
+    Write(SelectionTemplate, VariableDefinitions),
+}
+
+impl OperationTemplate {
+    /// Resolves every `$variable` placeholder in this template against `variables`, producing a
+    /// fully-resolved [`Operation`] ready for the query-graph builders.
+    ///
+    /// Errors if a declared variable is never referenced anywhere in the selection tree. Errors also
+    /// if a variable read while resolving - an argument of a selection that survives pruning, or the
+    /// `@skip`/`@include` condition of one of its nested selections - was never declared, has no
+    /// value in `variables`, or has a value that doesn't type-check against its declared type.
+    /// Arguments inside a pruned selection are not resolved, so none of these three checks applies
+    /// to them.
+    pub fn resolve_variables(&self, variables: &Variables) -> QueryParserResult<Operation> {
+        let (template, definitions, is_write) = match self {
+            Self::Read(template, definitions) => (template, definitions, false),
+            Self::Write(template, definitions) => (template, definitions, true),
+        };
+
+        let mut referenced = BTreeSet::new();
+        template.referenced_variables(&mut referenced);
+
+        if let Some(unused) = definitions.names().find(|name| !referenced.contains(*name)) {
+            return Err(QueryParserErrorKind::UnusedVariable(unused.to_owned()).into());
+        }
+
+        let selection = template.resolve(definitions, variables)?;
+
+        Ok(if is_write {
+            Operation::Write(selection)
+        } else {
+            Operation::Read(selection)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_template(build: impl FnOnce(&mut SelectionTemplate, &mut VariableDefinitions)) -> OperationTemplate {
+        let mut selection = SelectionTemplate::with_name("findOnePost");
+        let mut definitions = VariableDefinitions::new();
+
+        build(&mut selection, &mut definitions);
+
+        OperationTemplate::Read(selection, definitions)
+    }
+
+    #[test]
+    fn resolve_variables_substitutes_declared_and_used_variables() {
+        let template = read_template(|selection, definitions| {
+            definitions.insert("id", VariableType::Int);
+            selection.push_argument("id", TemplateValue::Variable("id".to_owned()), None);
+        });
+
+        let mut variables = Variables::new();
+        variables.insert("id", PrismaValue::Int(1));
+
+        let operation = template.resolve_variables(&variables).unwrap();
+        let selection = operation.into_read().unwrap();
+
+        assert_eq!(selection.arguments()[0], ("id".to_owned(), PrismaValue::Int(1)));
+    }
+
+    #[test]
+    fn resolve_variables_rejects_an_undeclared_variable() {
+        let template = read_template(|selection, _definitions| {
+            selection.push_argument("id", TemplateValue::Variable("id".to_owned()), None);
+        });
+
+        let err = template.resolve_variables(&Variables::new()).unwrap_err();
+
+        assert!(matches!(err.kind, QueryParserErrorKind::UndeclaredVariable(name) if name == "id"));
+    }
+
+    #[test]
+    fn resolve_variables_rejects_a_declared_but_unused_variable() {
+        let template = read_template(|_selection, definitions| {
+            definitions.insert("id", VariableType::Int);
+        });
+
+        let err = template.resolve_variables(&Variables::new()).unwrap_err();
+
+        assert!(matches!(err.kind, QueryParserErrorKind::UnusedVariable(name) if name == "id"));
+    }
+
+    #[test]
+    fn resolve_variables_rejects_a_missing_value() {
+        let template = read_template(|selection, definitions| {
+            definitions.insert("id", VariableType::Int);
+            selection.push_argument("id", TemplateValue::Variable("id".to_owned()), None);
+        });
+
+        let err = template.resolve_variables(&Variables::new()).unwrap_err();
+
+        assert!(matches!(err.kind, QueryParserErrorKind::MissingVariable(name) if name == "id"));
+    }
+
+    #[test]
+    fn resolve_variables_rejects_a_type_mismatch() {
+        let template = read_template(|selection, definitions| {
+            definitions.insert("id", VariableType::Int);
+            selection.push_argument("id", TemplateValue::Variable("id".to_owned()), None);
+        });
+
+        let mut variables = Variables::new();
+        variables.insert("id", PrismaValue::String("not-an-int".to_owned()));
+
+        let err = template.resolve_variables(&variables).unwrap_err();
+
+        assert!(matches!(err.kind, QueryParserErrorKind::VariableTypeMismatch { name, .. } if name == "id"));
+    }
+
+    #[test]
+    fn a_variable_only_referenced_inside_a_skipped_selection_still_counts_as_used() {
+        // Regression test: a variable referenced only inside a selection that `@skip`/`@include`
+        // ends up pruning is still statically "used", and must not raise `UnusedVariable`.
+        let template = read_template(|selection, definitions| {
+            definitions.insert("shouldSkip", VariableType::Boolean);
+
+            let mut nested = SelectionTemplate::with_name("comments");
+            nested.push_directive(Directive::Skip(TemplateValue::Variable("shouldSkip".to_owned()), None));
+            selection.push_nested_selection(nested);
+        });
+
+        let mut variables = Variables::new();
+        variables.insert("shouldSkip", PrismaValue::Boolean(true));
+
+        let operation = template.resolve_variables(&variables).unwrap();
+        let selection = operation.into_read().unwrap();
+
+        assert!(!selection.contains_nested_selection("comments"));
+    }
+}